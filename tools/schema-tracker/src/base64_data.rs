@@ -0,0 +1,108 @@
+//! A base64-carrying byte string that tolerates the handful of encodings
+//! different clients tend to emit, so sessions captured anywhere round-trip
+//! cleanly through schema validation.
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Base64-encoded bytes, e.g. the raw data of an [`ImageBlock`](crate::schema_types::ImageBlock).
+///
+/// Deserialization tries, in order, standard, URL-safe, URL-safe no-pad and
+/// no-pad standard base64, so documents produced by different clients all
+/// round-trip. Serialization always emits URL-safe no-pad.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base64Data(pub Vec<u8>);
+
+impl Serialize for Base64Data {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&URL_SAFE_NO_PAD.encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64Data {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        for engine in [&STANDARD, &URL_SAFE, &URL_SAFE_NO_PAD, &STANDARD_NO_PAD] {
+            if let Ok(bytes) = engine.decode(&encoded) {
+                return Ok(Base64Data(bytes));
+            }
+        }
+        Err(D::Error::custom(format!("{encoded:?} is not valid base64 in any known variant")))
+    }
+}
+
+impl JsonSchema for Base64Data {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Base64Data".to_owned()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("byte".to_owned()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(encoded: &str) -> Result<Base64Data, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::String(encoded.to_string()))
+    }
+
+    #[test]
+    fn decodes_standard_base64() {
+        // "hello>?" with standard padding, containing a `+` that would be
+        // rejected by URL-safe decoding.
+        assert_eq!(decode("aGVsbG8+Pw==").unwrap().0, b"hello>?");
+    }
+
+    #[test]
+    fn decodes_url_safe_base64() {
+        // Same bytes as above, but with the URL-safe `-`/`_` alphabet.
+        assert_eq!(decode("aGVsbG8-Pw==").unwrap().0, b"hello>?");
+    }
+
+    #[test]
+    fn decodes_url_safe_no_pad_base64() {
+        assert_eq!(decode("aGVsbG8-Pw").unwrap().0, b"hello>?");
+    }
+
+    #[test]
+    fn decodes_standard_no_pad_base64() {
+        assert_eq!(decode("aGVsbG8").unwrap().0, b"hello");
+    }
+
+    #[test]
+    fn rejects_non_base64_input() {
+        assert!(decode("not base64!!!").is_err());
+    }
+
+    #[test]
+    fn serializes_as_url_safe_no_pad() {
+        let data = Base64Data(b"hello>?".to_vec());
+        let value = serde_json::to_value(&data).unwrap();
+        assert_eq!(value, serde_json::Value::String("aGVsbG8-Pw".to_string()));
+    }
+
+    #[test]
+    fn round_trips_through_its_own_serialization() {
+        let data = Base64Data(vec![0, 1, 2, 255, 254]);
+        let value = serde_json::to_value(&data).unwrap();
+        let round_tripped: Base64Data = serde_json::from_value(value).unwrap();
+        assert_eq!(data, round_tripped);
+    }
+}
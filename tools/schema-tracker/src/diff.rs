@@ -0,0 +1,386 @@
+//! Structural comparison between two captured schema files, classified using
+//! the same "capabilities -> version tuple" idea used by the distant protocol
+//! crate: every field-level change is bucketed into MAJOR (breaking), MINOR
+//! (compatible addition/widening) or PATCH (cosmetic), and the buckets are
+//! folded into a single semver-style bump.
+
+use std::fs;
+use std::path::PathBuf;
+
+use eyre::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Severity of a single field-level change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FieldChange {
+    pub(crate) path: String,
+    pub(crate) severity: Severity,
+    pub(crate) description: String,
+}
+
+/// Run the `diff` subcommand: load two capture files, compare their schemas,
+/// print the verdict, and exit nonzero if a breaking change was found.
+pub fn run(old: PathBuf, new: PathBuf) -> Result<()> {
+    let old_capture: Value = serde_json::from_str(&fs::read_to_string(&old)?)?;
+    let new_capture: Value = serde_json::from_str(&fs::read_to_string(&new)?)?;
+
+    let old_schema = old_capture.get("schema").ok_or_else(|| {
+        eyre::eyre!("{} has no top-level \"schema\" field", old.display())
+    })?;
+    let new_schema = new_capture.get("schema").ok_or_else(|| {
+        eyre::eyre!("{} has no top-level \"schema\" field", new.display())
+    })?;
+
+    let (changes, (major, minor, patch)) = compare(old_schema, new_schema);
+
+    println!("📋 Schema diff: {} -> {}", old.display(), new.display());
+    if changes.is_empty() {
+        println!("   No differences found.");
+    } else {
+        for change in &changes {
+            let icon = match change.severity {
+                Severity::Major => "💥",
+                Severity::Minor => "➕",
+                Severity::Patch => "📝",
+            };
+            println!("   {} [{:?}] {}: {}", icon, change.severity, change.path, change.description);
+        }
+    }
+    println!("   Verdict: bump ({major}, {minor}, {patch})");
+
+    if major > 0 {
+        eyre::bail!("breaking schema change detected");
+    }
+
+    Ok(())
+}
+
+/// Compare two schemas field-by-field, returning the individual changes
+/// alongside the folded `(major, minor, patch)` bump. Shared with the
+/// `history` subcommand, which needs the same comparison between every
+/// pair of consecutive captures in a timeline.
+pub(crate) fn compare(old_schema: &Value, new_schema: &Value) -> (Vec<FieldChange>, (u8, u8, u8)) {
+    let mut changes = Vec::new();
+    diff_level(old_schema, new_schema, "", &mut changes);
+    let bump = summarize(&changes);
+    (changes, bump)
+}
+
+/// Fold individual field changes into a single `(major, minor, patch)` bump,
+/// where each component is 1 if any change of that severity was seen.
+fn summarize(changes: &[FieldChange]) -> (u8, u8, u8) {
+    let major = changes.iter().any(|c| c.severity == Severity::Major) as u8;
+    let minor = changes.iter().any(|c| c.severity == Severity::Minor) as u8;
+    let patch = changes.iter().any(|c| c.severity == Severity::Patch) as u8;
+    (major, minor, patch)
+}
+
+/// Compare the `properties`/`required` of two schema objects at the same
+/// path, recursing into nested `object`/`array` schemas.
+fn diff_level(old: &Value, new: &Value, prefix: &str, changes: &mut Vec<FieldChange>) {
+    let old_props = old.get("properties").and_then(Value::as_object);
+    let new_props = new.get("properties").and_then(Value::as_object);
+    let old_required = required_set(old);
+    let new_required = required_set(new);
+
+    let (Some(old_props), Some(new_props)) = (old_props, new_props) else {
+        return;
+    };
+
+    for (field, old_field_schema) in old_props {
+        let path = field_path(prefix, field);
+
+        let Some(new_field_schema) = new_props.get(field) else {
+            changes.push(FieldChange {
+                path,
+                severity: Severity::Major,
+                description: "field removed".to_string(),
+            });
+            continue;
+        };
+
+        diff_field(
+            &path,
+            old_field_schema,
+            new_field_schema,
+            old_required.contains(field.as_str()),
+            new_required.contains(field.as_str()),
+            changes,
+        );
+    }
+
+    for (field, _) in new_props {
+        if old_props.contains_key(field) {
+            continue;
+        }
+        let path = field_path(prefix, field);
+        if new_required.contains(field.as_str()) {
+            changes.push(FieldChange {
+                path,
+                severity: Severity::Major,
+                description: "new required field added".to_string(),
+            });
+        } else {
+            changes.push(FieldChange {
+                path,
+                severity: Severity::Minor,
+                description: "new optional field added".to_string(),
+            });
+        }
+    }
+}
+
+/// Compare a single field's schema between old and new, recording required-
+/// ness and type changes, then recurse into nested structures.
+fn diff_field(
+    path: &str,
+    old_field: &Value,
+    new_field: &Value,
+    was_required: bool,
+    is_required: bool,
+    changes: &mut Vec<FieldChange>,
+) {
+    if !was_required && is_required {
+        changes.push(FieldChange {
+            path: path.to_string(),
+            severity: Severity::Major,
+            description: "field became required".to_string(),
+        });
+    } else if was_required && !is_required {
+        changes.push(FieldChange {
+            path: path.to_string(),
+            severity: Severity::Minor,
+            description: "field became optional".to_string(),
+        });
+    }
+
+    let old_type = field_type(old_field);
+    let new_type = field_type(new_field);
+
+    if old_type != new_type {
+        if is_widening(old_field, new_field) {
+            changes.push(FieldChange {
+                path: path.to_string(),
+                severity: Severity::Minor,
+                description: format!("type widened from {old_type:?} to {new_type:?}"),
+            });
+        } else {
+            changes.push(FieldChange {
+                path: path.to_string(),
+                severity: Severity::Major,
+                description: format!("type changed from {old_type:?} to {new_type:?}"),
+            });
+        }
+    } else if old_field.get("description") != new_field.get("description") {
+        changes.push(FieldChange {
+            path: path.to_string(),
+            severity: Severity::Patch,
+            description: "description changed".to_string(),
+        });
+    }
+
+    if old_type.as_deref() == Some("object") || new_type.as_deref() == Some("object") {
+        diff_level(old_field, new_field, &format!("{path}."), changes);
+    }
+
+    if old_type.as_deref() == Some("array") || new_type.as_deref() == Some("array") {
+        if let (Some(old_items), Some(new_items)) = (old_field.get("items"), new_field.get("items")) {
+            diff_field(&format!("{path}[]"), old_items, new_items, false, false, changes);
+        }
+    }
+}
+
+/// A type change counts as a compatible widening when it's a numeric
+/// broadening (`integer` -> `number`) or the field gained a nullable
+/// branch.
+fn is_widening(old_field: &Value, new_field: &Value) -> bool {
+    let old_type = field_type(old_field);
+    let new_type = field_type(new_field);
+
+    if old_type.as_deref() == Some("integer") && new_type.as_deref() == Some("number") {
+        return true;
+    }
+
+    is_nullable(new_field) && !is_nullable(old_field)
+}
+
+/// Resolve a field schema's effective scalar type, treating nullability as
+/// metadata rather than a competing type however the schema represents it:
+/// schemars 0.8 emits `Option<T>` as `"type": [T, "null"]` (a JSON array),
+/// while a draft-07-style `anyOf` of `{"type": T}` / `{"type": "null"}` is
+/// also tolerated for schemas produced another way.
+fn field_type(field: &Value) -> Option<String> {
+    match field.get("type") {
+        Some(Value::String(t)) => return Some(t.clone()),
+        Some(Value::Array(types)) => {
+            return types.iter().filter_map(Value::as_str).find(|t| *t != "null").map(str::to_string);
+        }
+        _ => {}
+    }
+    field
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .and_then(|variants| {
+            variants
+                .iter()
+                .find_map(|v| v.get("type").and_then(Value::as_str).filter(|t| *t != "null"))
+        })
+        .map(str::to_string)
+}
+
+/// Whether a field schema admits `null`, via either nullability shape.
+fn is_nullable(field: &Value) -> bool {
+    match field.get("type") {
+        Some(Value::Array(types)) => return types.iter().any(|t| t.as_str() == Some("null")),
+        Some(Value::String(_)) => return false,
+        _ => {}
+    }
+    field
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .is_some_and(|variants| variants.iter().any(|v| v.get("type").and_then(Value::as_str) == Some("null")))
+}
+
+fn required_set(schema: &Value) -> std::collections::HashSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|arr| arr.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn field_path(prefix: &str, field: &str) -> String {
+    format!("{prefix}{field}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(properties: serde_json::Value, required: &[&str]) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    #[test]
+    fn new_required_field_is_major() {
+        let old = schema(serde_json::json!({}), &[]);
+        let new = schema(serde_json::json!({ "id": { "type": "string" } }), &["id"]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].severity, Severity::Major);
+        assert_eq!(bump, (1, 0, 0));
+    }
+
+    #[test]
+    fn new_optional_field_is_minor() {
+        let old = schema(serde_json::json!({}), &[]);
+        let new = schema(serde_json::json!({ "note": { "type": "string" } }), &[]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes[0].severity, Severity::Minor);
+        assert_eq!(bump, (0, 1, 0));
+    }
+
+    #[test]
+    fn integer_to_number_is_widening_minor() {
+        let old = schema(serde_json::json!({ "count": { "type": "integer" } }), &["count"]);
+        let new = schema(serde_json::json!({ "count": { "type": "number" } }), &["count"]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes[0].severity, Severity::Minor);
+        assert_eq!(bump, (0, 1, 0));
+    }
+
+    #[test]
+    fn string_to_integer_is_breaking_major() {
+        let old = schema(serde_json::json!({ "id": { "type": "string" } }), &["id"]);
+        let new = schema(serde_json::json!({ "id": { "type": "integer" } }), &["id"]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes[0].severity, Severity::Major);
+        assert_eq!(bump, (1, 0, 0));
+    }
+
+    #[test]
+    fn schemars_array_type_losing_required_is_minor_not_major() {
+        // schemars 0.8's `Option<T>` shape is `"type": [T, "null"]`. Before
+        // the fix, `get("type").and_then(Value::as_str)` returned `None`
+        // for that array, and the anyOf-only widening check couldn't see
+        // it either, so this classified as a MAJOR "type changed" instead
+        // of the MINOR "field became optional" it actually is.
+        let old = schema(serde_json::json!({ "note": { "type": "string" } }), &["note"]);
+        let new = schema(serde_json::json!({ "note": { "type": ["string", "null"] } }), &[]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].description, "field became optional");
+        assert_eq!(changes[0].severity, Severity::Minor);
+        assert_eq!(bump, (0, 1, 0));
+    }
+
+    #[test]
+    fn array_valued_type_change_on_optional_field_is_still_detected() {
+        // Regression: both sides used to resolve to `None` for any
+        // `Option`-wrapped field, so a real type change went unnoticed.
+        let old = schema(serde_json::json!({ "id": { "type": ["string", "null"] } }), &[]);
+        let new = schema(serde_json::json!({ "id": { "type": ["integer", "null"] } }), &[]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].severity, Severity::Major);
+        assert_eq!(bump, (1, 0, 0));
+    }
+
+    #[test]
+    fn nested_object_field_change_is_detected() {
+        let old = schema(
+            serde_json::json!({ "info": { "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] } }),
+            &["info"],
+        );
+        let new = schema(
+            serde_json::json!({ "info": { "type": "object", "properties": { "name": { "type": "integer" } }, "required": ["name"] } }),
+            &["info"],
+        );
+        let (changes, _) = compare(&old, &new);
+        assert!(changes.iter().any(|c| c.path == "info.name" && c.severity == Severity::Major));
+    }
+
+    #[test]
+    fn array_item_change_is_detected_under_bracket_path() {
+        let old = schema(
+            serde_json::json!({ "tags": { "type": "array", "items": { "type": "string" } } }),
+            &["tags"],
+        );
+        let new = schema(
+            serde_json::json!({ "tags": { "type": "array", "items": { "type": "integer" } } }),
+            &["tags"],
+        );
+        let (changes, _) = compare(&old, &new);
+        assert!(changes.iter().any(|c| c.path == "tags[]" && c.severity == Severity::Major));
+    }
+
+    #[test]
+    fn description_only_change_is_patch() {
+        let old = schema(serde_json::json!({ "id": { "type": "string", "description": "old" } }), &["id"]);
+        let new = schema(serde_json::json!({ "id": { "type": "string", "description": "new" } }), &["id"]);
+        let (changes, bump) = compare(&old, &new);
+        assert_eq!(changes[0].severity, Severity::Patch);
+        assert_eq!(bump, (0, 0, 1));
+    }
+
+    #[test]
+    fn identical_schemas_have_no_changes() {
+        let schema_a = schema(serde_json::json!({ "id": { "type": "string" } }), &["id"]);
+        let (changes, bump) = compare(&schema_a, &schema_a.clone());
+        assert!(changes.is_empty());
+        assert_eq!(bump, (0, 0, 0));
+    }
+}
@@ -0,0 +1,115 @@
+//! Detect divergence between the hand-maintained `schema_types` copy and the
+//! real upstream `chat_cli::cli::ConversationState`, by comparing the
+//! schemars-derived schema against the type-introspection schema generated
+//! from an actual instance of the upstream type.
+
+use eyre::Result;
+use serde_json::Value;
+
+use crate::{generate_schema_from_actual_type, generate_schema_with_schemars};
+
+/// Run the `drift` subcommand: generate both schemas, compare their
+/// top-level shape, and exit nonzero if they've diverged.
+pub fn run() -> Result<()> {
+    let schemars_schema = generate_schema_with_schemars()?;
+    let introspection_schema = generate_schema_from_actual_type()?;
+
+    let findings = compare_top_level(&schemars_schema, &introspection_schema);
+
+    println!("🔬 Drift check: schema_types (schemars) vs chat_cli::cli::ConversationState (introspection)");
+    if findings.is_empty() {
+        println!("   No drift detected.");
+        return Ok(());
+    }
+
+    for finding in &findings {
+        println!("   ⚠️  {finding}");
+    }
+
+    eyre::bail!("schema_types.rs has drifted from the real ConversationState ({} findings)", findings.len());
+}
+
+/// Compare the top-level `properties`/`required` sets of two schemas and
+/// describe every field present in only one, or whose inferred `type`
+/// disagrees.
+fn compare_top_level(schemars_schema: &Value, introspection_schema: &Value) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    let schemars_props = schemars_schema.get("properties").and_then(Value::as_object).cloned().unwrap_or_default();
+    let introspection_props = introspection_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    for (field, schemars_field) in &schemars_props {
+        match introspection_props.get(field) {
+            None => findings.push(format!("field `{field}` is in schema_types but missing from the actual type")),
+            Some(introspection_field) => {
+                let schemars_type = field_type(schemars_field);
+                let introspection_type = field_type(introspection_field);
+                if schemars_type != introspection_type {
+                    findings.push(format!(
+                        "field `{field}` type disagrees: schema_types says {schemars_type:?}, actual type says {introspection_type:?}"
+                    ));
+                }
+            }
+        }
+    }
+
+    for field in introspection_props.keys() {
+        if !schemars_props.contains_key(field) {
+            findings.push(format!("field `{field}` is on the actual type but missing from schema_types"));
+        }
+    }
+
+    let schemars_required = required_set(schemars_schema);
+    let introspection_required = required_set(introspection_schema);
+
+    for field in &schemars_required {
+        if !introspection_required.contains(field) {
+            findings.push(format!("field `{field}` is required in schema_types but not on the actual type"));
+        }
+    }
+    for field in &introspection_required {
+        if !schemars_required.contains(field) {
+            findings.push(format!("field `{field}` is required on the actual type but not in schema_types"));
+        }
+    }
+
+    findings
+}
+
+/// Resolve a field schema's effective type, treating a nullable branch the
+/// same as the underlying type so `Option<T>` doesn't register as a
+/// spurious mismatch. Nullability shows up in two shapes depending on the
+/// generator: schemars 0.8 emits `Option<T>` as `"type": [T, "null"]` (a
+/// JSON array), while a draft-07-style `anyOf` of `{"type": T}` /
+/// `{"type": "null"}` is also tolerated for schemas produced another way.
+/// A `$ref` branch carries no inline `type` and is not resolved, so an
+/// `Option` of a referenced struct (schemars' `anyOf` of `$ref` and `null`)
+/// resolves to `None` and is reported as disagreeing with the
+/// introspection side's `"object"`.
+fn field_type(field_schema: &Value) -> Option<String> {
+    match field_schema.get("type") {
+        Some(Value::String(t)) => return Some(t.clone()),
+        Some(Value::Array(types)) => {
+            return types.iter().filter_map(Value::as_str).find(|t| *t != "null").map(str::to_string);
+        }
+        _ => {}
+    }
+    field_schema.get("anyOf").and_then(Value::as_array).and_then(|variants| {
+        variants
+            .iter()
+            .find_map(|v| v.get("type").and_then(Value::as_str).filter(|t| *t != "null"))
+            .map(str::to_string)
+    })
+}
+
+fn required_set(schema: &Value) -> std::collections::HashSet<String> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|arr| arr.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
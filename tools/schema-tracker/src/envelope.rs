@@ -0,0 +1,43 @@
+//! The versioned shape of a capture file, so older archives in
+//! `tools/schema-tracker/schemas` stay parseable as the metadata this tool
+//! attaches evolves.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The `format_version` tag distinguishing capture-file shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchemaVersion {
+    /// The original ad-hoc capture object: no `format_version` field at all.
+    ///
+    /// `migrate::run` detects this shape by the *absence* of a
+    /// `format_version` field (and hands it to `migrate::upgrade_from_v1`)
+    /// rather than by constructing or matching this
+    /// variant, so it's never actually built in code -- it exists purely as
+    /// a documented, addressable tag for that format, and so a future
+    /// capture-file shape could reference it (e.g. in an error message or a
+    /// migration path table) without needing to invent a new variant.
+    #[allow(dead_code)]
+    V1,
+    /// The current envelope, with an explicit `format_version` tag and
+    /// `Option::is_none` metadata omitted rather than written as `null`.
+    V2,
+}
+
+/// The current capture-file format. Bump this alongside adding a new
+/// [`SchemaVersion`] variant and an upgrade path in `migrate`.
+pub const CURRENT_VERSION: SchemaVersion = SchemaVersion::V2;
+
+/// A strongly-typed capture file: a generated schema plus the metadata
+/// `capture` records about it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureEnvelope {
+    pub format_version: SchemaVersion,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    pub schema_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub schema: Value,
+}
@@ -0,0 +1,253 @@
+//! Walk a range of git commits/tags, capture the schema at each one, and
+//! assemble a timeline showing exactly which commit introduced a schema hash
+//! change. This is the automated counterpart to `capture`, which only ever
+//! snapshots the current working tree.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use eyre::{Result, WrapErr};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diff;
+
+#[derive(Debug, Serialize)]
+struct TimelineEntry {
+    git_commit: String,
+    timestamp: String,
+    schema_hash: Option<String>,
+    note: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff_from_previous: Option<Value>,
+}
+
+/// Run the `history` subcommand: enumerate refs in `from..to` (or matching
+/// `tag_glob`), capture the schema at each, and write a timeline JSON.
+pub fn run(from: Option<String>, to: Option<String>, tag_glob: Option<String>) -> Result<()> {
+    let original_ref = get_current_ref()?;
+    let stash_guard = StashGuard::stash_if_dirty()?;
+
+    let refs = list_refs(from.as_deref(), to.as_deref(), tag_glob.as_deref())?;
+    if refs.is_empty() {
+        eyre::bail!("no commits or tags found in the requested range");
+    }
+
+    let mut entries = Vec::new();
+    let mut previous_schema: Option<Value> = None;
+
+    for git_ref in &refs {
+        println!("🔍 Capturing schema at {git_ref}...");
+
+        match capture_schema_at_ref(git_ref) {
+            Ok((timestamp, schema_hash, schema)) => {
+                let diff_from_previous = previous_schema
+                    .as_ref()
+                    .map(|prev| summarize_diff(prev, &schema));
+
+                entries.push(TimelineEntry {
+                    git_commit: git_ref.clone(),
+                    timestamp,
+                    schema_hash: Some(schema_hash),
+                    note: "captured".to_string(),
+                    diff_from_previous,
+                });
+                previous_schema = Some(schema);
+            }
+            Err(e) => {
+                println!("   ⚠️  gap: {e}");
+                entries.push(TimelineEntry {
+                    git_commit: git_ref.clone(),
+                    timestamp: String::new(),
+                    schema_hash: None,
+                    note: format!("gap: {e}"),
+                    diff_from_previous: None,
+                });
+            }
+        }
+    }
+
+    checkout(&original_ref)?;
+    drop(stash_guard);
+
+    let timeline = serde_json::to_string_pretty(&entries)?;
+    let output_path = "tools/schema-tracker/schemas/history_timeline.json";
+    fs::create_dir_all("tools/schema-tracker/schemas")?;
+    fs::write(output_path, &timeline)?;
+
+    println!("✅ Timeline written: {output_path}");
+    println!("   {} entries ({} gaps)", entries.len(), entries.iter().filter(|e| e.schema_hash.is_none()).count());
+
+    Ok(())
+}
+
+/// Reduce a full field-level diff to the small JSON blob embedded per
+/// timeline entry: the verdict tuple and a short change list.
+fn summarize_diff(old_schema: &Value, new_schema: &Value) -> Value {
+    let (changes, (major, minor, patch)) = diff::compare(old_schema, new_schema);
+    serde_json::json!({
+        "verdict": { "major": major, "minor": minor, "patch": patch },
+        "changes": changes,
+    })
+}
+
+/// Check out `git_ref`, run `capture --schemars`, and read back the schema
+/// that was just written, or return an error if checkout/build fails.
+fn capture_schema_at_ref(git_ref: &str) -> Result<(String, String, Value)> {
+    checkout(git_ref).wrap_err("checkout failed")?;
+
+    let before: std::collections::HashSet<_> = list_capture_files()?.into_iter().collect();
+
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", "schema-tracker", "--", "capture", "--schemars"])
+        .status()
+        .wrap_err("failed to spawn cargo run")?;
+    if !status.success() {
+        eyre::bail!("build/capture failed for {git_ref}");
+    }
+
+    let after = list_capture_files()?;
+    let new_file = after
+        .into_iter()
+        .find(|f| !before.contains(f))
+        .ok_or_else(|| eyre::eyre!("capture did not produce a new schema file"))?;
+
+    let capture: Value = serde_json::from_str(&fs::read_to_string(&new_file)?)?;
+    let timestamp = capture
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let schema_hash = capture
+        .get("schema_hash")
+        .and_then(Value::as_str)
+        .ok_or_else(|| eyre::eyre!("capture file missing schema_hash"))?
+        .to_string();
+    let schema = capture
+        .get("schema")
+        .cloned()
+        .ok_or_else(|| eyre::eyre!("capture file missing schema"))?;
+
+    Ok((timestamp, schema_hash, schema))
+}
+
+fn list_capture_files() -> Result<Vec<PathBuf>> {
+    let dir = "tools/schema-tracker/schemas";
+    fs::create_dir_all(dir)?;
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// List the commits or tags to walk, in chronological order.
+fn list_refs(from: Option<&str>, to: Option<&str>, tag_glob: Option<&str>) -> Result<Vec<String>> {
+    if let Some(glob) = tag_glob {
+        let output = Command::new("git")
+            .args(["tag", "-l", glob, "--sort=creatordate"])
+            .output()
+            .wrap_err("failed to list tags")?;
+        if !output.status.success() {
+            eyre::bail!("git tag -l failed");
+        }
+        return Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(str::to_string)
+            .filter(|l| !l.is_empty())
+            .collect());
+    }
+
+    let range = match (from, to) {
+        (Some(f), Some(t)) => format!("{f}..{t}"),
+        (Some(f), None) => format!("{f}..HEAD"),
+        (None, Some(t)) => t.to_string(),
+        (None, None) => "HEAD".to_string(),
+    };
+
+    let output = Command::new("git")
+        .args(["log", "--reverse", "--format=%H", &range])
+        .output()
+        .wrap_err("failed to list commits")?;
+    if !output.status.success() {
+        eyre::bail!("git log failed for range {range}");
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::to_string)
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+fn checkout(git_ref: &str) -> Result<()> {
+    let status = Command::new("git")
+        .args(["checkout", "--quiet", git_ref])
+        .status()
+        .wrap_err("failed to spawn git checkout")?;
+    if !status.success() {
+        eyre::bail!("git checkout {git_ref} failed");
+    }
+    Ok(())
+}
+
+fn get_git_commit() -> Result<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    } else {
+        Err(eyre::eyre!("Failed to get git commit"))
+    }
+}
+
+/// The ref to restore once `history` is done walking: the current branch
+/// name, so the caller ends up back where they started rather than in
+/// detached HEAD, falling back to the bare commit SHA if HEAD is already
+/// detached (e.g. `get_git_commit`'s `rev-parse` would apply either way).
+fn get_current_ref() -> Result<String> {
+    let output = Command::new("git").args(["symbolic-ref", "-q", "--short", "HEAD"]).output()?;
+    if output.status.success() {
+        let branch = String::from_utf8(output.stdout)?.trim().to_string();
+        if !branch.is_empty() {
+            return Ok(branch);
+        }
+    }
+    get_git_commit()
+}
+
+/// Stashes a dirty working tree for the duration of `history`, and restores
+/// it when dropped so a failed run never leaves work stashed.
+struct StashGuard {
+    stashed: bool,
+}
+
+impl StashGuard {
+    fn stash_if_dirty() -> Result<Self> {
+        let status = Command::new("git").args(["status", "--porcelain"]).output()?;
+        let dirty = !String::from_utf8(status.stdout)?.trim().is_empty();
+
+        if dirty {
+            println!("💾 Working tree is dirty, stashing before walking history...");
+            let status = Command::new("git")
+                .args(["stash", "push", "--include-untracked", "--message", "schema-tracker history"])
+                .status()?;
+            if !status.success() {
+                eyre::bail!("failed to stash dirty working tree");
+            }
+        }
+
+        Ok(Self { stashed: dirty })
+    }
+}
+
+impl Drop for StashGuard {
+    fn drop(&mut self) {
+        if self.stashed {
+            let _ = Command::new("git").args(["stash", "pop"]).status();
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Upgrade an older capture file to the current [`CaptureEnvelope`] shape.
+
+use std::fs;
+use std::path::PathBuf;
+
+use eyre::Result;
+use serde_json::Value;
+
+use crate::envelope::{CaptureEnvelope, CURRENT_VERSION};
+
+/// Run the `migrate` subcommand: read `path`, upgrade it to the current
+/// envelope shape if needed, and rewrite it in place.
+pub fn run(path: PathBuf) -> Result<()> {
+    let raw: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+
+    let envelope = if raw.get("format_version").is_some() {
+        let envelope: CaptureEnvelope = serde_json::from_value(raw)?;
+        if envelope.format_version == CURRENT_VERSION {
+            println!("✅ {} is already {:?}, nothing to do", path.display(), CURRENT_VERSION);
+            return Ok(());
+        }
+        envelope
+    } else {
+        upgrade_from_v1(&raw, &path)?
+    };
+
+    fs::write(&path, serde_json::to_string_pretty(&envelope)?)?;
+    println!("✅ Migrated {} to {:?}", path.display(), envelope.format_version);
+
+    Ok(())
+}
+
+/// Upgrade the original ad-hoc capture object (no `format_version` field)
+/// into a [`CaptureEnvelope`].
+fn upgrade_from_v1(raw: &Value, path: &std::path::Path) -> Result<CaptureEnvelope> {
+    Ok(CaptureEnvelope {
+        format_version: CURRENT_VERSION,
+        timestamp: raw
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre::eyre!("{} has no \"timestamp\" field", path.display()))?
+            .to_string(),
+        git_commit: raw.get("git_commit").and_then(Value::as_str).map(str::to_string),
+        schema_hash: raw
+            .get("schema_hash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre::eyre!("{} has no \"schema_hash\" field", path.display()))?
+            .to_string(),
+        note: raw.get("note").and_then(Value::as_str).map(str::to_string),
+        schema: raw
+            .get("schema")
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("{} has no \"schema\" field", path.display()))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("schema_tracker_migrate_test_{name}_{}.json", std::process::id()))
+    }
+
+    #[test]
+    fn upgrades_a_well_formed_v1_file() {
+        let raw = serde_json::json!({
+            "timestamp": "2024-01-01T00:00:00Z",
+            "git_commit": "abc123",
+            "schema_hash": "deadbeef",
+            "note": "a note",
+            "schema": { "type": "object" }
+        });
+
+        let envelope = upgrade_from_v1(&raw, std::path::Path::new("test.json")).unwrap();
+
+        assert_eq!(envelope.format_version, CURRENT_VERSION);
+        assert_eq!(envelope.timestamp, "2024-01-01T00:00:00Z");
+        assert_eq!(envelope.git_commit.as_deref(), Some("abc123"));
+        assert_eq!(envelope.schema_hash, "deadbeef");
+        assert_eq!(envelope.note.as_deref(), Some("a note"));
+        assert_eq!(envelope.schema, serde_json::json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn upgrade_errors_on_missing_timestamp() {
+        let raw = serde_json::json!({ "schema_hash": "deadbeef", "schema": {} });
+        let err = upgrade_from_v1(&raw, std::path::Path::new("test.json")).unwrap_err();
+        assert!(err.to_string().contains("timestamp"));
+    }
+
+    #[test]
+    fn upgrade_errors_on_missing_schema_hash() {
+        let raw = serde_json::json!({ "timestamp": "2024-01-01T00:00:00Z", "schema": {} });
+        let err = upgrade_from_v1(&raw, std::path::Path::new("test.json")).unwrap_err();
+        assert!(err.to_string().contains("schema_hash"));
+    }
+
+    #[test]
+    fn upgrade_errors_on_missing_schema() {
+        let raw = serde_json::json!({ "timestamp": "2024-01-01T00:00:00Z", "schema_hash": "deadbeef" });
+        let err = upgrade_from_v1(&raw, std::path::Path::new("test.json")).unwrap_err();
+        assert!(err.to_string().contains("schema"));
+    }
+
+    #[test]
+    fn run_migrates_a_v1_file_in_place() {
+        let path = temp_path("v1");
+        let raw = serde_json::json!({
+            "timestamp": "2024-01-01T00:00:00Z",
+            "schema_hash": "deadbeef",
+            "schema": { "type": "object" }
+        });
+        fs::write(&path, serde_json::to_string(&raw).unwrap()).unwrap();
+
+        run(path.clone()).unwrap();
+
+        let migrated: CaptureEnvelope = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(migrated.format_version, CURRENT_VERSION);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn run_is_a_no_op_on_an_already_current_file() {
+        let path = temp_path("v2");
+        let envelope = CaptureEnvelope {
+            format_version: CURRENT_VERSION,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            git_commit: None,
+            schema_hash: "deadbeef".to_string(),
+            note: None,
+            schema: serde_json::json!({ "type": "object" }),
+        };
+        fs::write(&path, serde_json::to_string(&envelope).unwrap()).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        run(path.clone()).unwrap();
+
+        let after = fs::read_to_string(&path).unwrap();
+        assert_eq!(before, after);
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -30,6 +30,8 @@ use schemars::JsonSchema;
 use std::collections::{HashMap, VecDeque};
 use chrono::{DateTime, Utc};
 
+use crate::base64_data::Base64Data;
+
 /// Schema-aware copy of ConversationState
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConversationState {
@@ -171,7 +173,7 @@ pub struct FileLineTracker {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImageBlock {
     pub image_type: String,
-    pub data: String,
+    pub data: Base64Data,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -0,0 +1,189 @@
+//! Runtime validation of real `ConversationState` JSON documents against a
+//! previously captured schema, following the AsyncAPI pattern of gating
+//! runtime validation behind an env toggle so it can be disabled for perf.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+/// Env var that disables validation entirely when set to `"0"`.
+const VALIDATE_ENV_VAR: &str = "SCHEMA_TRACKER_VALIDATE";
+
+/// Run the `validate` subcommand: load the schema from a capture file and
+/// check every document under `input` against it.
+pub fn run(schema: PathBuf, input: PathBuf, strict: bool) -> Result<()> {
+    if std::env::var(VALIDATE_ENV_VAR).as_deref() == Ok("0") {
+        println!("⏭️  {VALIDATE_ENV_VAR}=0, skipping validation");
+        return Ok(());
+    }
+
+    let capture: Value = serde_json::from_str(&fs::read_to_string(&schema)?)?;
+    let schema_json = capture
+        .get("schema")
+        .ok_or_else(|| eyre::eyre!("{} has no top-level \"schema\" field", schema.display()))?;
+
+    let compiled = JSONSchema::compile(schema_json)
+        .map_err(|e| eyre::eyre!("invalid schema in {}: {e}", schema.display()))?;
+
+    let mut documents = Vec::new();
+    collect_documents(&input, &mut documents)?;
+
+    let mut had_errors = false;
+
+    for doc_path in documents {
+        let instance: Value = serde_json::from_str(&fs::read_to_string(&doc_path)?)?;
+
+        let mut errors = Vec::new();
+        if let Err(validation_errors) = compiled.validate(&instance) {
+            for error in validation_errors {
+                errors.push(format!("{} ({})", error, error.instance_path));
+            }
+        }
+        if strict {
+            collect_unknown_properties(schema_json, &instance, "", &mut errors);
+        }
+
+        if errors.is_empty() {
+            println!("✅ {}", doc_path.display());
+        } else {
+            had_errors = true;
+            println!("❌ {}", doc_path.display());
+            for error in errors {
+                println!("   {error}");
+            }
+        }
+    }
+
+    if had_errors {
+        eyre::bail!("one or more documents failed validation");
+    }
+
+    Ok(())
+}
+
+/// Resolve `path` to a list of JSON files: itself if it's a file, or every
+/// `*.json` entry if it's a directory.
+fn collect_documents(path: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+            if entry_path.extension().is_some_and(|ext| ext == "json") {
+                out.push(entry_path);
+            }
+        }
+        out.sort();
+    } else {
+        out.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Recursively check that `instance` has no properties absent from
+/// `schema`'s `properties`, recording a pointer-style path for each extra.
+/// Array instances are walked element-by-element against the schema's
+/// `items` definition, so an unknown property nested inside array entries
+/// is caught when that definition lists its `properties` inline. A `$ref`
+/// subschema (as schemars emits for the `history` and `transcript` entry
+/// types) is not followed, and properties below it are not checked.
+fn collect_unknown_properties(schema: &Value, instance: &Value, pointer: &str, errors: &mut Vec<String>) {
+    if let Some(instance_arr) = instance.as_array() {
+        let Some(items_schema) = schema.get("items") else {
+            return;
+        };
+        for (index, item) in instance_arr.iter().enumerate() {
+            collect_unknown_properties(items_schema, item, &format!("{pointer}/{index}"), errors);
+        }
+        return;
+    }
+
+    let (Some(schema_props), Some(instance_obj)) = (
+        schema.get("properties").and_then(Value::as_object),
+        instance.as_object(),
+    ) else {
+        return;
+    };
+
+    for (key, value) in instance_obj {
+        let field_pointer = format!("{pointer}/{key}");
+        match schema_props.get(key) {
+            None => errors.push(format!("additional property not allowed ({field_pointer})")),
+            Some(field_schema) => collect_unknown_properties(field_schema, value, &field_pointer, errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `history`-shaped schema: an array of objects with one known
+    /// property, matching the `ConversationState.history` field this
+    /// check exists to validate.
+    fn history_like_schema() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "history": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "user": { "type": "string" } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn unknown_property_nested_inside_an_array_element_is_caught() {
+        let schema = history_like_schema();
+        let instance = serde_json::json!({
+            "history": [
+                { "user": "hi" },
+                { "user": "hi", "unexpected": "field" }
+            ]
+        });
+
+        let mut errors = Vec::new();
+        collect_unknown_properties(&schema, &instance, "", &mut errors);
+
+        assert_eq!(errors, vec!["additional property not allowed (/history/1/unexpected)"]);
+    }
+
+    #[test]
+    fn array_elements_with_no_unknown_properties_pass() {
+        let schema = history_like_schema();
+        let instance = serde_json::json!({ "history": [{ "user": "hi" }] });
+
+        let mut errors = Vec::new();
+        collect_unknown_properties(&schema, &instance, "", &mut errors);
+
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn unknown_top_level_property_is_still_caught() {
+        let schema = history_like_schema();
+        let instance = serde_json::json!({ "history": [], "extra": true });
+
+        let mut errors = Vec::new();
+        collect_unknown_properties(&schema, &instance, "", &mut errors);
+
+        assert_eq!(errors, vec!["additional property not allowed (/extra)"]);
+    }
+
+    #[test]
+    fn array_with_no_items_schema_is_skipped_rather_than_panicking() {
+        let schema = serde_json::json!({ "type": "array" });
+        let instance = serde_json::json!([{ "unexpected": "field" }]);
+
+        let mut errors = Vec::new();
+        collect_unknown_properties(&schema, &instance, "", &mut errors);
+
+        assert!(errors.is_empty());
+    }
+}